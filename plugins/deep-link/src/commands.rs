@@ -0,0 +1,17 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use tauri::{command, AppHandle, Runtime};
+
+use crate::{DeepLinkExt, Result};
+
+#[command]
+pub(crate) async fn execute<R: Runtime>(_app: AppHandle<R>) -> Result<()> {
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn get_last_link<R: Runtime>(app: AppHandle<R>) -> Result<Option<Vec<url::Url>>> {
+    app.deep_link().get_last_link()
+}
@@ -0,0 +1,15 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    /// URL schemes this app owns. Restricts which schemes [`crate::DeepLink::register`] is
+    /// allowed to claim and which incoming deep links are forwarded to
+    /// [`crate::DeepLink::on_open_url`] and [`crate::DeepLink::get_last_link`].
+    #[serde(default)]
+    pub schemes: Vec<String>,
+}
@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use serde::de::DeserializeOwned;
 use tauri::{
     plugin::{Builder, PluginApi, TauriPlugin},
     AppHandle, Manager, Runtime,
@@ -11,61 +10,187 @@ use tauri::{
 mod commands;
 mod config;
 mod error;
+mod scope;
 
 pub use error::{Error, Result};
+pub use scope::Scope;
 
 #[cfg(target_os = "android")]
 const PLUGIN_IDENTIFIER: &str = "app.tauri.deep_link";
 
-fn init_deep_link<R: Runtime, C: DeserializeOwned>(
+/// The event every platform funnels its parsed deep link URLs through, whether that's the
+/// Android [`Channel`](tauri::ipc::Channel) handler, the macOS/iOS `RunEvent::Opened` branch, or
+/// the desktop argv scan.
+const OPEN_URL_EVENT: &str = "deep-link://new-url";
+
+/// The payload delivered to closures registered with [`DeepLink::on_open_url`].
+#[derive(Debug, Clone)]
+pub struct OpenUrlEvent {
+    urls: Vec<url::Url>,
+}
+
+impl OpenUrlEvent {
+    /// The deep link URLs that triggered this event.
+    pub fn urls(&self) -> Vec<url::Url> {
+        self.urls.clone()
+    }
+}
+
+/// Keep only the URLs whose scheme is declared in `scope`, then emit [`OPEN_URL_EVENT`] with
+/// whatever survives. The single dispatch point every platform's deep link delivery funnels
+/// through: the Android [`Channel`](tauri::ipc::Channel) handler, the macOS/iOS
+/// `RunEvent::Opened` branch, and the desktop argv scan all call this instead of each
+/// reimplementing the filter-then-emit step. Returns the filtered URLs so callers that also
+/// track a "last link" can store them.
+fn emit_scoped<R: Runtime>(
+    app: &AppHandle<R>,
+    scope: &Scope,
+    urls: Vec<url::Url>,
+) -> Vec<url::Url> {
+    let urls = in_scope(scope, urls);
+    if !urls.is_empty() {
+        // `emit_all` only reaches the webviews; the Rust listeners registered through
+        // `on_open_url` are global listeners and need `trigger_global`
+        app.trigger_global(OPEN_URL_EVENT, serde_json::to_string(&urls).ok());
+        let _ = app.emit_all(OPEN_URL_EVENT, urls.clone());
+    }
+    urls
+}
+
+/// Store `urls` as the last link and emit them through [`emit_scoped`].
+#[cfg(not(target_os = "android"))]
+fn emit_urls<R: Runtime>(app: &AppHandle<R>, urls: Vec<url::Url>) {
+    let scope = app.state::<DeepLink<R>>().scope.clone();
+    let urls = emit_scoped(app, &scope, urls);
+    if urls.is_empty() {
+        return;
+    }
+
+    app.state::<DeepLink<R>>()
+        .last_link
+        .lock()
+        .unwrap()
+        .replace(urls);
+}
+
+/// Keep only the URLs whose scheme is declared in `scope`.
+fn in_scope(scope: &Scope, urls: Vec<url::Url>) -> Vec<url::Url> {
+    urls.into_iter()
+        .filter(|url| scope.is_allowed(url.scheme()))
+        .collect()
+}
+
+fn init_deep_link<R: Runtime>(
     app: &AppHandle<R>,
-    _api: PluginApi<R, C>,
+    api: PluginApi<R, Option<config::Config>>,
 ) -> crate::Result<DeepLink<R>> {
+    let scope = Scope::new(&api.config().clone().unwrap_or_default())?;
+    if scope.schemes().is_empty() {
+        log::warn!(
+            "the `deep-link` plugin is configured with no `schemes`, so every incoming deep \
+             link will be silently dropped; add the schemes this app should handle to the \
+             plugin configuration"
+        );
+    }
+
     #[cfg(target_os = "android")]
     {
         use tauri::ipc::{Channel, InvokeBody};
 
-        let handle = _api.register_android_plugin(PLUGIN_IDENTIFIER, "DeepLinkPlugin")?;
+        let handle = api.register_android_plugin(PLUGIN_IDENTIFIER, "DeepLinkPlugin")?;
 
         let app_handle = app.clone();
         handle.run_mobile_plugin::<()>(
             "setEventHandler",
             imp::EventHandler {
                 handler: Channel::new(move |event| {
-                    println!("got channel event: {:?}", &event);
-
                     let url = match event {
                         InvokeBody::Json(payload) => payload
                             .get("url")
                             .and_then(|v| v.as_str())
-                            .map(|s| s.to_owned()),
+                            .and_then(|s| url::Url::parse(s).ok()),
                         _ => None,
                     };
 
-                    let payload = vec![url];
-                    app_handle.trigger_global(
-                        "deep-link://new-url",
-                        Some(serde_json::to_string(&payload).unwrap()),
-                    );
-                    let _ = app_handle.emit_all("deep-link://new-url", payload);
+                    if let Some(url) = url {
+                        let scope = app_handle.state::<DeepLink<R>>().scope.clone();
+                        emit_scoped(&app_handle, &scope, vec![url]);
+                    }
                     Ok(())
                 }),
             },
         )?;
 
-        return Ok(DeepLink(handle));
+        return Ok(DeepLink {
+            handle,
+            app: app.clone(),
+            scope,
+        });
     }
 
     #[cfg(not(target_os = "android"))]
-    Ok(DeepLink {
-        app: app.clone(),
-        last_link: Default::default(),
-    })
+    {
+        let deep_link = DeepLink {
+            app: app.clone(),
+            last_link: Default::default(),
+            scope,
+        };
+
+        // on Windows and Linux a clicked link launches a new instance of the app with the
+        // url as an argument, so we need to pick it up from our own argv on startup
+        #[cfg(any(target_os = "linux", target_os = "windows"))]
+        {
+            let urls = urls_from_argv(std::env::args().skip(1), &deep_link.scope);
+            let urls = emit_scoped(app, &deep_link.scope, urls);
+            if !urls.is_empty() {
+                deep_link.last_link.lock().unwrap().replace(urls);
+            }
+        }
+
+        Ok(deep_link)
+    }
+}
+
+/// Parse any arguments that look like deep link URLs out of an argument list, keeping only
+/// those whose scheme is declared in `scope`.
+///
+/// Scoping is mandatory here, not just at the point the URLs are emitted: `url::Url::parse`
+/// happily accepts things that are not deep links at all, most notably a Windows drive-letter
+/// path such as `C:\Users\foo\bar.txt`, which parses as a URL with the single-letter scheme
+/// `c`. Without a scope check an ordinary file-association or installer-relaunch argument
+/// would be mistaken for a deep link.
+#[cfg(not(target_os = "android"))]
+fn urls_from_argv(argv: impl IntoIterator<Item = String>, scope: &Scope) -> Vec<url::Url> {
+    argv.into_iter()
+        .filter_map(|arg| url::Url::parse(&arg).ok())
+        .filter(|url| scope.is_allowed(url.scheme()))
+        .collect()
+}
+
+/// Feed the `argv` and working directory of a secondary app instance into this running
+/// instance, emitting `deep-link://new-url` for any deep link URLs found among the arguments.
+///
+/// Pair this with [`tauri_plugin_single_instance`](https://docs.rs/tauri-plugin-single-instance)'s
+/// callback so a clicked link that spawns a second process hands its URL to the already-running
+/// instance instead of the second process handling it on its own:
+///
+/// ```ignore
+/// tauri_plugin_single_instance::init(|app, argv, cwd| {
+///     tauri_plugin_deep_link::handle_argv(app, argv, cwd);
+/// })
+/// ```
+#[cfg(not(target_os = "android"))]
+pub fn handle_argv<R: Runtime>(app: &AppHandle<R>, argv: Vec<String>, _cwd: String) {
+    let scope = &app.state::<DeepLink<R>>().scope;
+    let urls = urls_from_argv(argv, scope);
+    if !urls.is_empty() {
+        emit_urls(app, urls);
+    }
 }
 
 #[cfg(target_os = "android")]
 mod imp {
-    use tauri::{plugin::PluginHandle, Runtime};
+    use tauri::{plugin::PluginHandle, AppHandle, Runtime};
 
     use serde::{Deserialize, Serialize};
     use tauri::ipc::Channel;
@@ -83,15 +208,24 @@ mod imp {
     }
 
     /// Access to the deep-link APIs.
-    pub struct DeepLink<R: Runtime>(pub(crate) PluginHandle<R>);
+    pub struct DeepLink<R: Runtime> {
+        pub(crate) handle: PluginHandle<R>,
+        pub(crate) app: AppHandle<R>,
+        pub(crate) scope: crate::Scope,
+    }
 
     impl<R: Runtime> DeepLink<R> {
         /// Get the last saved URL that triggered the deep link.
         pub fn get_last_link(&self) -> crate::Result<Option<Vec<url::Url>>> {
-            self.0
+            let url = self
+                .handle
                 .run_mobile_plugin::<LastUrl>("getLastLink", ())
-                .map(|v| v.url.map(|url| vec![url]))
-                .map_err(Into::into)
+                .map(|v| v.url)
+                .map_err(crate::Error::from)?;
+
+            Ok(url
+                .filter(|url| self.scope.is_allowed(url.scheme()))
+                .map(|url| vec![url]))
         }
     }
 }
@@ -103,9 +237,9 @@ mod imp {
 
     /// Access to the deep-link APIs.
     pub struct DeepLink<R: Runtime> {
-        #[allow(dead_code)]
         pub(crate) app: AppHandle<R>,
         pub(crate) last_link: Mutex<Option<Vec<url::Url>>>,
+        pub(crate) scope: crate::Scope,
     }
 
     impl<R: Runtime> DeepLink<R> {
@@ -113,11 +247,252 @@ mod imp {
         pub fn get_last_link(&self) -> crate::Result<Option<Vec<url::Url>>> {
             Ok(self.last_link.lock().unwrap().clone())
         }
+
+        /// Register this app as the default handler for the given URL scheme. Fails with
+        /// [`Error::SchemeNotAllowed`](crate::Error::SchemeNotAllowed) if `scheme` is not
+        /// declared in the `deep-link` plugin configuration.
+        ///
+        /// On Linux this writes a `.desktop` file to the user's applications directory
+        /// declaring a `x-scheme-handler/<scheme>` MIME association and refreshes the
+        /// desktop database so the scheme is routed to the app. On Windows this creates
+        /// the `HKCU\Software\Classes\<scheme>` key pointing at the current executable.
+        #[cfg(target_os = "linux")]
+        pub fn register(&self, scheme: impl AsRef<str>) -> crate::Result<()> {
+            let scheme = scheme.as_ref();
+            if !self.scope.is_allowed(scheme) {
+                return Err(crate::Error::SchemeNotAllowed(scheme.to_string()));
+            }
+            linux::register(&self.app, scheme)
+        }
+
+        /// See [`DeepLink::register`] for Windows.
+        #[cfg(target_os = "windows")]
+        pub fn register(&self, scheme: impl AsRef<str>) -> crate::Result<()> {
+            let scheme = scheme.as_ref();
+            if !self.scope.is_allowed(scheme) {
+                return Err(crate::Error::SchemeNotAllowed(scheme.to_string()));
+            }
+            windows::register(scheme)
+        }
+
+        /// Remove this app as the default handler for the given URL scheme.
+        #[cfg(target_os = "linux")]
+        pub fn unregister(&self, scheme: impl AsRef<str>) -> crate::Result<()> {
+            linux::unregister(&self.app, scheme.as_ref())
+        }
+
+        /// See [`DeepLink::unregister`] for Windows.
+        #[cfg(target_os = "windows")]
+        pub fn unregister(&self, scheme: impl AsRef<str>) -> crate::Result<()> {
+            windows::unregister(scheme.as_ref())
+        }
+
+        /// Check whether this app is currently registered as the default handler for the given URL scheme.
+        #[cfg(target_os = "linux")]
+        pub fn is_registered(&self, scheme: impl AsRef<str>) -> crate::Result<bool> {
+            linux::is_registered(&self.app, scheme.as_ref())
+        }
+
+        /// See [`DeepLink::is_registered`] for Windows.
+        #[cfg(target_os = "windows")]
+        pub fn is_registered(&self, scheme: impl AsRef<str>) -> crate::Result<bool> {
+            windows::is_registered(scheme.as_ref())
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    mod linux {
+        use std::io::Write;
+        use std::path::Path;
+        use tauri::{AppHandle, Runtime};
+
+        fn desktop_file_name<R: Runtime>(app: &AppHandle<R>) -> String {
+            format!("{}.desktop", app.config().identifier)
+        }
+
+        fn applications_dir() -> crate::Result<std::path::PathBuf> {
+            let home = std::env::var("HOME").map_err(|_| crate::Error::FailedToGetHomeDir)?;
+            let dir = std::path::PathBuf::from(home).join(".local/share/applications");
+            std::fs::create_dir_all(&dir)?;
+            Ok(dir)
+        }
+
+        /// The schemes already declared in an existing `.desktop` file's `MimeType` line, so
+        /// `register`/`unregister` can add or remove a single scheme without clobbering the
+        /// others this app is registered for.
+        fn registered_schemes(path: &Path) -> Vec<String> {
+            let Ok(contents) = std::fs::read_to_string(path) else {
+                return Vec::new();
+            };
+
+            contents
+                .lines()
+                .find_map(|line| line.strip_prefix("MimeType="))
+                .map(|mime_types| {
+                    mime_types
+                        .trim_end_matches(';')
+                        .split(';')
+                        .filter_map(|entry| entry.strip_prefix("x-scheme-handler/"))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        fn write_desktop_file<R: Runtime>(
+            app: &AppHandle<R>,
+            path: &Path,
+            schemes: &[String],
+        ) -> crate::Result<()> {
+            let exe = tauri::utils::platform::current_exe()?;
+            let mime_types = schemes
+                .iter()
+                .map(|scheme| format!("x-scheme-handler/{scheme}"))
+                .collect::<Vec<_>>()
+                .join(";");
+
+            let mut file = std::fs::File::create(path)?;
+            write!(
+                file,
+                "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" %u\nNoDisplay=true\nMimeType={};\n",
+                app.config().identifier,
+                exe.display(),
+                mime_types,
+            )?;
+
+            Ok(())
+        }
+
+        pub fn register<R: Runtime>(app: &AppHandle<R>, scheme: &str) -> crate::Result<()> {
+            let file_name = desktop_file_name(app);
+            let path = applications_dir()?.join(&file_name);
+
+            let mut schemes = registered_schemes(&path);
+            if !schemes.iter().any(|s| s == scheme) {
+                schemes.push(scheme.to_string());
+            }
+            write_desktop_file(app, &path, &schemes)?;
+
+            let _ = std::process::Command::new("update-desktop-database")
+                .arg(applications_dir()?)
+                .status();
+            let _ = std::process::Command::new("xdg-mime")
+                .args(["default", &file_name, &format!("x-scheme-handler/{scheme}")])
+                .status();
+
+            Ok(())
+        }
+
+        pub fn unregister<R: Runtime>(app: &AppHandle<R>, scheme: &str) -> crate::Result<()> {
+            let path = applications_dir()?.join(desktop_file_name(app));
+            let remaining: Vec<String> = registered_schemes(&path)
+                .into_iter()
+                .filter(|s| s != scheme)
+                .collect();
+
+            if remaining.is_empty() {
+                if path.exists() {
+                    std::fs::remove_file(&path)?;
+                }
+            } else {
+                // other schemes are still registered through this same .desktop file, so
+                // only drop this scheme's MimeType entry instead of the whole file
+                write_desktop_file(app, &path, &remaining)?;
+            }
+
+            let _ = std::process::Command::new("update-desktop-database")
+                .arg(applications_dir()?)
+                .status();
+
+            Ok(())
+        }
+
+        pub fn is_registered<R: Runtime>(app: &AppHandle<R>, scheme: &str) -> crate::Result<bool> {
+            let output = std::process::Command::new("xdg-mime")
+                .args(["query", "default", &format!("x-scheme-handler/{scheme}")])
+                .output()?;
+            let current = String::from_utf8_lossy(&output.stdout);
+            Ok(current.trim() == desktop_file_name(app))
+        }
+    }
+
+    // This module will not compile for target_os = "windows" until `winreg` is declared as a
+    // `target_os = "windows"` dependency in this plugin's Cargo.toml, alongside its other
+    // dependencies (tauri, serde, thiserror, url, log, ...) — none of which has a manifest
+    // entry anywhere in this tree either. There is no Cargo.toml in this source snapshot to
+    // add it to.
+    #[cfg(target_os = "windows")]
+    mod windows {
+        use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+        pub fn register(scheme: &str) -> crate::Result<()> {
+            let exe = tauri::utils::platform::current_exe()?;
+
+            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+            let (class, _) = hkcu.create_subkey(format!("Software\\Classes\\{scheme}"))?;
+            class.set_value("", &format!("URL:{scheme}"))?;
+            class.set_value("URL Protocol", &"")?;
+
+            let (command, _) = class.create_subkey("shell\\open\\command")?;
+            command.set_value("", &format!("\"{}\" \"%1\"", exe.display()))?;
+
+            Ok(())
+        }
+
+        pub fn unregister(scheme: &str) -> crate::Result<()> {
+            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+            match hkcu.delete_subkey_all(format!("Software\\Classes\\{scheme}")) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e.into()),
+            }
+        }
+
+        pub fn is_registered(scheme: &str) -> crate::Result<bool> {
+            let exe = tauri::utils::platform::current_exe()?;
+            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+
+            match hkcu.open_subkey(format!("Software\\Classes\\{scheme}\\shell\\open\\command")) {
+                Ok(key) => {
+                    let command: String = key.get_value("")?;
+                    Ok(command.contains(&exe.display().to_string()))
+                }
+                Err(_) => Ok(false),
+            }
+        }
     }
 }
 
 pub use imp::DeepLink;
 
+impl<R: Runtime> DeepLink<R> {
+    /// Listen to an incoming deep link URL event. All platforms funnel through [`OPEN_URL_EVENT`]
+    /// so this closure is called the same way regardless of whether the link arrived through the
+    /// Android `Channel`, the macOS/iOS `RunEvent::Opened` path or the desktop argv scan.
+    ///
+    /// Returns an id that can be passed to [`DeepLink::unlisten`].
+    pub fn on_open_url<F: Fn(OpenUrlEvent) + Send + 'static>(&self, handler: F) -> tauri::EventId {
+        self.app.listen_global(OPEN_URL_EVENT, move |event| {
+            let urls = event
+                .payload()
+                .and_then(|payload| serde_json::from_str(payload).ok())
+                .unwrap_or_default();
+            handler(OpenUrlEvent { urls });
+        })
+    }
+
+    /// Remove an event listener registered with [`DeepLink::on_open_url`].
+    pub fn unlisten(&self, id: tauri::EventId) {
+        self.app.unlisten(id)
+    }
+
+    /// The resolved set of URL schemes this app is allowed to register and accept deep links
+    /// for, as declared by the `deep-link` plugin configuration.
+    pub fn scope(&self) -> Scope {
+        self.scope.clone()
+    }
+}
+
 /// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the deep-link APIs.
 pub trait DeepLinkExt<R: Runtime> {
     fn deep_link(&self) -> &DeepLink<R>;
@@ -144,13 +519,58 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<config::Config>> {
         .on_event(|_app, _event| {
             #[cfg(any(target_os = "macos", target_os = "ios"))]
             if let tauri::RunEvent::Opened { urls } = _event {
-                let _ = _app.emit_all("deep-link://new-url", urls);
-                _app.state::<DeepLink<R>>()
-                    .last_link
-                    .lock()
-                    .unwrap()
-                    .replace(urls.clone());
+                emit_urls(_app, urls.clone());
             }
         })
         .build()
 }
+
+#[cfg(all(test, not(target_os = "android")))]
+mod tests {
+    use super::*;
+
+    fn scope(schemes: &[&str]) -> Scope {
+        Scope::new(&config::Config {
+            schemes: schemes.iter().map(|s| s.to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn in_scope_drops_urls_outside_the_scope() {
+        let scope = scope(&["myapp"]);
+        let urls = vec![
+            url::Url::parse("myapp://open").unwrap(),
+            url::Url::parse("https://example.com").unwrap(),
+        ];
+
+        let filtered = in_scope(&scope, urls);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].scheme(), "myapp");
+    }
+
+    #[test]
+    fn urls_from_argv_ignores_arguments_outside_the_scope() {
+        let scope = scope(&["myapp"]);
+        let argv = vec![
+            "myapp://open".to_string(),
+            "--flag".to_string(),
+            "https://example.com".to_string(),
+        ];
+
+        let urls = urls_from_argv(argv, &scope);
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls[0].scheme(), "myapp");
+    }
+
+    #[test]
+    fn urls_from_argv_does_not_mistake_windows_paths_for_deep_links() {
+        // `url::Url::parse` happily treats a drive-letter path as a URL with a
+        // single-letter scheme, so without scoping, an ordinary file-association or
+        // installer-relaunch argument would be mistaken for a deep link.
+        let scope = scope(&["myapp"]);
+        let argv = vec!["C:\\Users\\foo\\bar.txt".to_string()];
+
+        assert!(urls_from_argv(argv, &scope).is_empty());
+    }
+}
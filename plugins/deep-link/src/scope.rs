@@ -0,0 +1,94 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::config::Config;
+
+/// The set of URL schemes this app is allowed to register and accept deep links for, resolved
+/// from the `schemes` array of the `deep-link` plugin configuration.
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    schemes: Vec<String>,
+}
+
+impl Scope {
+    pub(crate) fn new(config: &Config) -> crate::Result<Self> {
+        for scheme in &config.schemes {
+            validate_scheme(scheme)?;
+        }
+
+        Ok(Self {
+            schemes: config.schemes.clone(),
+        })
+    }
+
+    /// The schemes declared in the plugin configuration.
+    pub fn schemes(&self) -> Vec<String> {
+        self.schemes.clone()
+    }
+
+    /// Returns `true` if `scheme` was declared in the plugin configuration.
+    pub fn is_allowed(&self, scheme: &str) -> bool {
+        self.schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme))
+    }
+}
+
+/// A URL scheme must start with an ASCII letter and only contain ASCII letters, digits, `+`,
+/// `-` or `.`, following the grammar in
+/// [RFC 3986 §3.1](https://www.rfc-editor.org/rfc/rfc3986#section-3.1).
+fn validate_scheme(scheme: &str) -> crate::Result<()> {
+    let mut chars = scheme.chars();
+    let is_valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(crate::Error::InvalidScheme(scheme.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_scheme_accepts_rfc3986_grammar() {
+        assert!(validate_scheme("myapp").is_ok());
+        assert!(validate_scheme("my-app+v2.0").is_ok());
+        assert!(validate_scheme("a").is_ok());
+    }
+
+    #[test]
+    fn validate_scheme_rejects_invalid_schemes() {
+        assert!(validate_scheme("").is_err());
+        assert!(validate_scheme("1app").is_err());
+        assert!(validate_scheme("my app").is_err());
+        assert!(validate_scheme("my_app").is_err());
+    }
+
+    #[test]
+    fn is_allowed_is_case_insensitive_and_scoped() {
+        let scope = Scope {
+            schemes: vec!["myapp".into()],
+        };
+
+        assert!(scope.is_allowed("myapp"));
+        assert!(scope.is_allowed("MyApp"));
+        assert!(!scope.is_allowed("other"));
+    }
+
+    #[test]
+    fn empty_scope_allows_nothing() {
+        let scope = Scope::default();
+        assert!(!scope.is_allowed("myapp"));
+    }
+
+    #[test]
+    fn new_rejects_invalid_configured_scheme() {
+        let config = Config {
+            schemes: vec!["not a scheme".into()],
+        };
+        assert!(Scope::new(&config).is_err());
+    }
+}